@@ -0,0 +1,117 @@
+// Agent 核心类型与运行时状态：IPC 命令层围绕这些类型构建请求/响应契约
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri_plugin_shell::process::CommandChild;
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentReply {
+    pub session_id: String,
+    pub content: String,
+}
+
+/// 增量 token 事件，通过 `agent://token/{task_id}` 频道推送给前端。
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenEvent {
+    pub delta: String,
+    pub done: bool,
+}
+
+/// 任务正常结束时通过 `agent://done` 广播。
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentDoneEvent {
+    pub task_id: String,
+    pub reply: AgentReply,
+}
+
+/// 任务失败时通过 `agent://error` 广播。
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentErrorEvent {
+    pub task_id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionMeta {
+    pub id: String,
+    pub title: String,
+    pub created_at: u64,
+}
+
+impl SessionMeta {
+    fn new(id: String) -> Self {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            title: id.clone(),
+            id,
+            created_at,
+        }
+    }
+}
+
+/// 一个正在运行的 agent 任务持有的两个协作任务：`producer` 产出增量，
+/// `forwarder` 把增量转发为前端事件。取消时两者都要中止，否则 `forwarder`
+/// 会在 `producer` 被中止后继续把已缓冲的增量当作正常结束广播出去。
+pub struct TaskHandles {
+    pub producer: JoinHandle<()>,
+    pub forwarder: JoinHandle<()>,
+}
+
+impl TaskHandles {
+    pub fn abort(&self) {
+        self.producer.abort();
+        self.forwarder.abort();
+    }
+}
+
+/// 一个正在运行的 `run_tool` 调用持有的句柄：`watcher` 是读取子进程输出、
+/// 转发事件、在超时后杀掉子进程的那个任务；`child` 用 `Arc<Mutex<Option<_>>>`
+/// 包一层，是因为 `CommandChild::kill` 会消费自身，而 `watcher`（超时路径）
+/// 和 `cancel_task`/Quit（用户路径）都可能是第一个尝试杀掉它的一方。
+pub struct ToolHandle {
+    pub watcher: JoinHandle<()>,
+    pub child: Arc<Mutex<Option<CommandChild>>>,
+}
+
+impl ToolHandle {
+    pub fn abort(&self) {
+        self.watcher.abort();
+        if let Ok(mut child) = self.child.lock() {
+            if let Some(child) = child.take() {
+                let _ = child.kill();
+            }
+        }
+    }
+}
+
+/// 运行中的 agent 任务、`run_tool` 子进程与已知会话，跨 `invoke` 调用共享。
+pub struct AgentState {
+    pub sessions: Mutex<Vec<SessionMeta>>,
+    pub tasks: Mutex<HashMap<String, TaskHandles>>,
+    pub tool_tasks: Mutex<HashMap<String, ToolHandle>>,
+}
+
+impl AgentState {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(Vec::new()),
+            tasks: Mutex::new(HashMap::new()),
+            tool_tasks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 记录一个会话，如果是第一次见到这个 `session_id` 的话。
+    pub fn ensure_session(&self, session_id: &str) {
+        let mut sessions = self.sessions.lock().unwrap();
+        if !sessions.iter().any(|s| s.id == session_id) {
+            sessions.push(SessionMeta::new(session_id.to_string()));
+        }
+    }
+}
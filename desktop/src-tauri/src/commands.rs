@@ -0,0 +1,219 @@
+// `invoke` 命令层：前端通过这些命令与 Rust 后端交互
+
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::agent::{
+    AgentDoneEvent, AgentErrorEvent, AgentReply, AgentState, SessionMeta, TaskHandles, TokenEvent,
+};
+use crate::config::AppConfig;
+use crate::shortcut;
+use crate::tools;
+
+/// 增量 channel 的缓冲区大小：生产速度快于前端消费时，`send` 会在这里等待，
+/// 形成背压，避免无限堆积未投递的 token。
+const TOKEN_CHANNEL_CAPACITY: usize = 16;
+
+pub struct ConfigState(pub Mutex<AppConfig>);
+
+/// 设置新的全局热键：先注册新绑定，确认成功后再注销旧绑定，最后持久化。
+/// 如果新的 accelerator 注册失败，旧热键会继续保留，不会让用户在本次会话里
+/// 彻底失去全局热键。
+#[tauri::command]
+pub async fn set_global_hotkey(
+    app: AppHandle,
+    state: State<'_, ConfigState>,
+    accelerator: String,
+) -> Result<(), String> {
+    let previous = {
+        let config = state.0.lock().map_err(|e| e.to_string())?;
+        config.global_hotkey.clone()
+    };
+
+    if previous != accelerator {
+        shortcut::register(&app, &accelerator)?;
+        let _ = shortcut::unregister(&app, &previous);
+    }
+
+    let mut config = state.0.lock().map_err(|e| e.to_string())?;
+    config.global_hotkey = accelerator;
+    config.save(&app).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// 设置关闭窗口时是否最小化到托盘而不是退出进程，并持久化。
+#[tauri::command]
+pub async fn set_close_to_tray(
+    app: AppHandle,
+    state: State<'_, ConfigState>,
+    close_to_tray: bool,
+) -> Result<(), String> {
+    let mut config = state.0.lock().map_err(|e| e.to_string())?;
+    config.close_to_tray = close_to_tray;
+    config.save(&app).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 启动一次 agent 任务并立即返回 `task_id`；结果通过事件流增量推送，
+/// webview 线程不会被长时间运行的模型调用阻塞。
+///
+/// 前端应在调用后监听 `agent://token/{task_id}`（增量 `{ delta, done }`）、
+/// `agent://done`（完整回复）与 `agent://error`（失败原因）。任务以
+/// `task_id` 为键记录在 [`AgentState`] 中，`cancel_task` 可据此中止它，
+/// 中止后两个协作任务都会停止，不会再投递任何事件。任务正常结束时
+/// `forwarder` 会自己把这个条目从 `AgentState.tasks` 中移除，避免常驻
+/// 托盘应用里堆积已完成任务的句柄，也避免之后的 `cancel_task` 误伤一个
+/// 早已结束的任务并广播虚假的 `agent://error`。
+///
+/// `forwarder` 在移除条目前会先等待 `registered_rx`，确保父协程已经把
+/// `TaskHandles` 写进 `state.tasks`——否则一个瞬间完成的任务可能在父协程
+/// 执行 `insert` 之前就跑完并 `remove`，导致紧随其后的 `insert` 把一条
+/// 再也不会被清理的陈旧记录留在任务表里。
+#[tauri::command]
+pub async fn run_agent(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    prompt: String,
+    session_id: String,
+) -> Result<String, String> {
+    state.ensure_session(&session_id);
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(TOKEN_CHANNEL_CAPACITY);
+    let (registered_tx, registered_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let producer = tokio::spawn(async move {
+        // 占位实现：真正的模型推理在这里逐 token 产出增量，目前按空白切分原样回显。
+        for word in prompt.split_whitespace() {
+            if tx.send(format!("{word} ")).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    let forwarder_app = app.clone();
+    let forwarder_task_id = task_id.clone();
+    let forwarder_session_id = session_id.clone();
+    let forwarder = tokio::spawn(async move {
+        let token_event = format!("agent://token/{forwarder_task_id}");
+        let mut content = String::new();
+
+        while let Some(delta) = rx.recv().await {
+            content.push_str(&delta);
+            let _ = forwarder_app.emit(
+                &token_event,
+                TokenEvent {
+                    delta,
+                    done: false,
+                },
+            );
+        }
+
+        let _ = forwarder_app.emit(
+            &token_event,
+            TokenEvent {
+                delta: String::new(),
+                done: true,
+            },
+        );
+        let _ = forwarder_app.emit(
+            "agent://done",
+            AgentDoneEvent {
+                task_id: forwarder_task_id.clone(),
+                reply: AgentReply {
+                    session_id: forwarder_session_id,
+                    content,
+                },
+            },
+        );
+
+        // 等父协程确认已经把本任务登记进任务表后，再把它摘掉，避免竞态。
+        let _ = registered_rx.await;
+        if let Some(agent_state) = forwarder_app.try_state::<AgentState>() {
+            if let Ok(mut tasks) = agent_state.tasks.lock() {
+                tasks.remove(&forwarder_task_id);
+            }
+        }
+    });
+
+    state
+        .tasks
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(task_id.clone(), TaskHandles { producer, forwarder });
+    let _ = registered_tx.send(());
+
+    Ok(task_id)
+}
+
+/// 取消一个正在运行的任务：可能是 `run_agent` 的 producer/forwarder 协作
+/// 任务，也可能是 `run_tool` 启动的子进程，使其不再投递任何
+/// `agent://token`、`agent://done` 或 `agent://tool-done` 事件。
+#[tauri::command]
+pub async fn cancel_task(
+    app: AppHandle,
+    state: State<'_, AgentState>,
+    task_id: String,
+) -> Result<(), String> {
+    let agent_handles = state.tasks.lock().map_err(|e| e.to_string())?.remove(&task_id);
+
+    let aborted = if let Some(handles) = agent_handles {
+        handles.abort();
+        true
+    } else if let Some(tool_handle) = state
+        .tool_tasks
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&task_id)
+    {
+        tool_handle.abort();
+        true
+    } else {
+        false
+    };
+
+    if aborted {
+        let _ = app.emit(
+            "agent://error",
+            AgentErrorEvent {
+                task_id,
+                message: "task cancelled".to_string(),
+            },
+        );
+    }
+    Ok(())
+}
+
+/// 列出所有已知的会话。
+#[tauri::command]
+pub async fn list_sessions(state: State<'_, AgentState>) -> Result<Vec<SessionMeta>, String> {
+    state.sessions.lock().map_err(|e| e.to_string()).map(|s| s.clone())
+}
+
+/// 运行一个白名单内的本地命令，拒绝任何未加入 `allowed_tools` 的二进制。
+/// 立即返回 `task_id`，输出通过与 agent token 相同的 `agent://token/{task_id}`
+/// 频道实时转发；最终结果在进程退出（或因超时被杀掉）后通过
+/// `agent://tool-done` 广播。子进程登记在 `AgentState.tool_tasks` 里，
+/// 同一个 `task_id` 也可以交给 `cancel_task` 中途杀掉。
+#[tauri::command]
+pub async fn run_tool(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    agent_state: State<'_, AgentState>,
+    name: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+) -> Result<String, String> {
+    let (allowed, timeout_secs) = {
+        let config = config_state.0.lock().map_err(|e| e.to_string())?;
+        (config.is_tool_allowed(&name), config.tool_timeout_secs)
+    };
+
+    if !allowed {
+        return Err(format!("tool `{name}` is not in the allowlist"));
+    }
+
+    tools::run(&app, &agent_state, &name, args, cwd, timeout_secs).await
+}
@@ -0,0 +1,128 @@
+// 持久化配置：保存在应用配置目录下的 config.json
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+const CONFIG_FILE_NAME: &str = "config.json";
+
+fn default_accelerator() -> String {
+    "CommandOrControl+Space".to_string()
+}
+
+fn default_close_to_tray() -> bool {
+    true
+}
+
+/// 默认允许 agent 通过 `run_tool` 调用的本地命令。
+fn default_allowed_tools() -> Vec<String> {
+    vec!["git".to_string(), "cargo".to_string()]
+}
+
+fn default_tool_timeout_secs() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub global_hotkey: String,
+    pub close_to_tray: bool,
+    pub allowed_tools: Vec<String>,
+    pub tool_timeout_secs: u64,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            global_hotkey: default_accelerator(),
+            close_to_tray: default_close_to_tray(),
+            allowed_tools: default_allowed_tools(),
+            tool_timeout_secs: default_tool_timeout_secs(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// 判断 `name` 是否允许通过 `run_tool` 执行。这是 agent 与宿主 shell
+    /// 之间的安全边界，所以单独抽成一个可独立测试的检查，而不是在每个调用
+    /// 处内联这个判断。
+    pub fn is_tool_allowed(&self, name: &str) -> bool {
+        self.allowed_tools.iter().any(|allowed| allowed == name)
+    }
+
+    fn path(app: &AppHandle) -> tauri::Result<PathBuf> {
+        let dir = app.path().app_config_dir()?;
+        fs::create_dir_all(&dir)?;
+        Ok(dir.join(CONFIG_FILE_NAME))
+    }
+
+    /// 从磁盘加载配置，不存在或解析失败时回退到默认值。
+    pub fn load(app: &AppHandle) -> Self {
+        Self::path(app)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, app: &AppHandle) -> tauri::Result<()> {
+        let path = Self::path(app)?;
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| tauri::Error::Anyhow(anyhow::anyhow!(e)))?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_tool_allowed_matches_allowlist_only() {
+        let config = AppConfig {
+            allowed_tools: vec!["git".to_string()],
+            ..AppConfig::default()
+        };
+
+        assert!(config.is_tool_allowed("git"));
+        assert!(!config.is_tool_allowed("rm"));
+        assert!(!config.is_tool_allowed(""));
+    }
+
+    #[test]
+    fn default_allowlist_is_git_and_cargo() {
+        let config = AppConfig::default();
+        assert_eq!(config.allowed_tools, vec!["git".to_string(), "cargo".to_string()]);
+        assert!(config.is_tool_allowed("cargo"));
+        assert!(!config.is_tool_allowed("curl"));
+    }
+
+    #[test]
+    fn serde_roundtrip_preserves_all_fields() {
+        let config = AppConfig {
+            global_hotkey: "Alt+Space".to_string(),
+            close_to_tray: false,
+            allowed_tools: vec!["git".to_string(), "node".to_string()],
+            tool_timeout_secs: 5,
+        };
+
+        let json = serde_json::to_string(&config).unwrap();
+        let restored: AppConfig = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.global_hotkey, config.global_hotkey);
+        assert_eq!(restored.close_to_tray, config.close_to_tray);
+        assert_eq!(restored.allowed_tools, config.allowed_tools);
+        assert_eq!(restored.tool_timeout_secs, config.tool_timeout_secs);
+    }
+
+    #[test]
+    fn missing_fields_in_serialized_config_fall_back_to_defaults() {
+        let restored: AppConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(restored.global_hotkey, AppConfig::default().global_hotkey);
+        assert_eq!(restored.tool_timeout_secs, AppConfig::default().tool_timeout_secs);
+    }
+}
@@ -2,14 +2,47 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
 use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
+
+mod agent;
+mod commands;
+mod config;
+mod shortcut;
+mod tools;
+mod tray;
+
+use agent::AgentState;
+use commands::ConfigState;
+use config::AppConfig;
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(AgentState::new())
+        .invoke_handler(tauri::generate_handler![
+            commands::set_global_hotkey,
+            commands::set_close_to_tray,
+            commands::run_agent,
+            commands::cancel_task,
+            commands::list_sessions,
+            commands::run_tool,
+        ])
         .setup(|app| {
-            // 系统托盘点击：显示/隐藏窗口
+            // 从配置文件加载用户设置（全局热键、关闭行为等）
+            let config = AppConfig::load(app.handle());
+            let close_to_tray = config.close_to_tray;
+            if let Err(e) = shortcut::register(app.handle(), &config.global_hotkey) {
+                eprintln!("failed to register global hotkey: {e}");
+            }
+            app.manage(ConfigState(Mutex::new(config)));
+
+            // 系统托盘：点击图标（不区分鼠标键）显示/隐藏窗口，另外给图标挂上菜单
             #[cfg(desktop)]
             {
                 let window = app.get_webview_window("main").unwrap();
@@ -28,6 +61,44 @@ fn main() {
                         _ => {}
                     }
                 });
+
+                let (menu, close_to_tray_item) = tray::build_menu(app.handle(), close_to_tray)?;
+                if let Some(tray_icon) = app.tray_by_id("main") {
+                    tray_icon.set_menu(Some(menu))?;
+                }
+                app.on_menu_event(move |app, event| {
+                    tray::handle_menu_event(app, &close_to_tray_item, event);
+                });
+
+                // 关闭按钮拦截：默认最小化到托盘，保持后台任务与全局热键可用
+                let close_app_handle = app.handle().clone();
+                let close_window = window.clone();
+                let close_notice_shown = Arc::new(AtomicBool::new(false));
+
+                window.on_window_event(move |event| {
+                    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                        let close_to_tray = close_app_handle
+                            .try_state::<ConfigState>()
+                            .and_then(|s| s.0.lock().ok().map(|c| c.close_to_tray))
+                            .unwrap_or(true);
+
+                        if close_to_tray {
+                            api.prevent_close();
+                            let _ = close_window.hide();
+
+                            // 只在本次会话里第一次关闭窗口时弹一次系统通知，避免用户每次
+                            // 点关闭按钮都被打扰。
+                            if !close_notice_shown.swap(true, Ordering::Relaxed) {
+                                let _ = close_app_handle
+                                    .notification()
+                                    .builder()
+                                    .title("Jarvis")
+                                    .body("Jarvis is still running in the background")
+                                    .show();
+                            }
+                        }
+                    }
+                });
             }
 
             Ok(())
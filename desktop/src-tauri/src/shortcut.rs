@@ -0,0 +1,65 @@
+// 全局热键：在任意位置唤出/隐藏主窗口，类似 Spotlight
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+/// 切换主窗口的显示状态：可见则隐藏，否则显示并聚焦。
+pub fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 注册 `accelerator` 为全局热键，按下后切换主窗口。
+pub fn register(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    let shortcut: Shortcut = accelerator.parse().map_err(|e| format!("{e}"))?;
+
+    let app_handle = app.clone();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |_app, _shortcut, event| {
+            if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                toggle_main_window(&app_handle);
+            }
+        })
+        .map_err(|e| format!("{e}"))
+}
+
+/// 注销之前注册的热键。
+pub fn unregister(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    let shortcut: Shortcut = accelerator.parse().map_err(|e| format!("{e}"))?;
+    app.global_shortcut()
+        .unregister(shortcut)
+        .map_err(|e| format!("{e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use tauri_plugin_global_shortcut::Shortcut;
+
+    #[test]
+    fn parses_default_accelerator() {
+        assert!("CommandOrControl+Space".parse::<Shortcut>().is_ok());
+    }
+
+    #[test]
+    fn parses_accelerators_with_multiple_modifiers() {
+        assert!("Ctrl+Shift+Space".parse::<Shortcut>().is_ok());
+    }
+
+    #[test]
+    fn rejects_accelerator_with_no_key() {
+        assert!("CommandOrControl+".parse::<Shortcut>().is_err());
+    }
+
+    #[test]
+    fn rejects_garbage_accelerator() {
+        assert!("not an accelerator".parse::<Shortcut>().is_err());
+    }
+}
@@ -0,0 +1,137 @@
+// 沙盒化的本地工具执行：只允许调用配置中白名单列出的命令
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::ShellExt;
+
+use crate::agent::{AgentState, TokenEvent, ToolHandle};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolResult {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+}
+
+/// 进程退出后广播一次，携带 [`run`] 返回的 `task_id`，方便前端把它和自己
+/// 订阅的 `agent://token/{task_id}` 频道对应起来。
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolDoneEvent {
+    pub task_id: String,
+    pub result: ToolResult,
+}
+
+/// 运行一个已通过白名单校验的命令，立即返回 `task_id`（复用 `run_agent`
+/// 先 spawn 再返回 id 的模式）。输出通过与 agent token 相同的
+/// `agent://token/{task_id}` 频道实时转发；`watcher` 在本函数返回之前就可能
+/// 开始发送，调用方订阅之前发出的增量不会重发。进程退出（或因超时被杀掉）
+/// 后，包含完整 stdout/stderr 的 `ToolResult` 通过 `agent://tool-done` 广播。
+///
+/// 子进程的 `watcher` 任务与 [`ToolHandle`] 会登记进 `state.tool_tasks`，
+/// 这样 `cancel_task` 和托盘 Quit 才能真正杀掉一个还在运行的本地命令，
+/// 而不是只能等超时。`watcher` 在自己摘除任务表条目前会先等待
+/// `registered_rx`，避免出现任务还没登记完就已经跑完并 remove 的竞态
+/// （参见 `run_agent` 里同样的处理）。
+pub async fn run(
+    app: &AppHandle,
+    state: &AgentState,
+    name: &str,
+    args: Vec<String>,
+    cwd: Option<String>,
+    timeout_secs: u64,
+) -> Result<String, String> {
+    let mut command = app.shell().command(name).args(&args);
+    if let Some(cwd) = cwd {
+        command = command.current_dir(cwd);
+    }
+
+    let (mut rx, child) = command.spawn().map_err(|e| e.to_string())?;
+    let child = Arc::new(Mutex::new(Some(child)));
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+    let spawned_task_id = task_id.clone();
+    let watcher_app = app.clone();
+    let watcher_child = child.clone();
+    let (registered_tx, registered_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let watcher = tokio::spawn(async move {
+        let token_event = format!("agent://token/{spawned_task_id}");
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        let mut exit_code = None;
+        let mut timed_out = false;
+
+        let deadline = tokio::time::sleep(Duration::from_secs(timeout_secs));
+        tokio::pin!(deadline);
+
+        loop {
+            tokio::select! {
+                event = rx.recv() => {
+                    match event {
+                        Some(CommandEvent::Stdout(bytes)) => {
+                            let delta = String::from_utf8_lossy(&bytes).into_owned();
+                            stdout.push_str(&delta);
+                            let _ = watcher_app.emit(&token_event, TokenEvent { delta, done: false });
+                        }
+                        Some(CommandEvent::Stderr(bytes)) => {
+                            let delta = String::from_utf8_lossy(&bytes).into_owned();
+                            stderr.push_str(&delta);
+                            let _ = watcher_app.emit(&token_event, TokenEvent { delta, done: false });
+                        }
+                        Some(CommandEvent::Terminated(payload)) => {
+                            exit_code = payload.code;
+                            break;
+                        }
+                        Some(_) => {}
+                        None => break,
+                    }
+                }
+                _ = &mut deadline => {
+                    timed_out = true;
+                    if let Ok(mut child) = watcher_child.lock() {
+                        if let Some(child) = child.take() {
+                            let _ = child.kill();
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+
+        let _ = watcher_app.emit(&token_event, TokenEvent { delta: String::new(), done: true });
+        let _ = watcher_app.emit(
+            "agent://tool-done",
+            ToolDoneEvent {
+                task_id: spawned_task_id.clone(),
+                result: ToolResult {
+                    stdout,
+                    stderr,
+                    exit_code,
+                    timed_out,
+                },
+            },
+        );
+
+        let _ = registered_rx.await;
+        if let Some(agent_state) = watcher_app.try_state::<AgentState>() {
+            if let Ok(mut tool_tasks) = agent_state.tool_tasks.lock() {
+                tool_tasks.remove(&spawned_task_id);
+            }
+        }
+    });
+
+    state
+        .tool_tasks
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(task_id.clone(), ToolHandle { watcher, child });
+    let _ = registered_tx.send(());
+
+    Ok(task_id)
+}
@@ -0,0 +1,84 @@
+// 托盘菜单：显示/隐藏、新建会话、设置、切换关闭行为、退出
+
+use tauri::{
+    menu::{CheckMenuItem, Menu, MenuEvent, MenuItem},
+    AppHandle, Emitter, Manager, Wry,
+};
+
+use crate::agent::AgentState;
+use crate::commands::ConfigState;
+use crate::shortcut;
+
+const MENU_ID_SHOW_HIDE: &str = "show_hide";
+const MENU_ID_NEW_CHAT: &str = "new_chat";
+const MENU_ID_SETTINGS: &str = "settings";
+const MENU_ID_CLOSE_TO_TRAY: &str = "close_to_tray";
+const MENU_ID_QUIT: &str = "quit";
+
+/// 构建托盘右键菜单，连同 "Close to Tray" 复选项一起返回，
+/// 调用方需要持有后者以便在点击时读取/回写它的勾选状态。
+pub fn build_menu(app: &AppHandle, close_to_tray: bool) -> tauri::Result<(Menu<Wry>, CheckMenuItem<Wry>)> {
+    let show_hide = MenuItem::with_id(app, MENU_ID_SHOW_HIDE, "Show/Hide Window", true, None::<&str>)?;
+    let new_chat = MenuItem::with_id(app, MENU_ID_NEW_CHAT, "New Chat", true, None::<&str>)?;
+    let settings = MenuItem::with_id(app, MENU_ID_SETTINGS, "Settings", true, None::<&str>)?;
+    let close_to_tray_item = CheckMenuItem::with_id(
+        app,
+        MENU_ID_CLOSE_TO_TRAY,
+        "Close to Tray",
+        true,
+        close_to_tray,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, MENU_ID_QUIT, "Quit", true, None::<&str>)?;
+
+    let menu = Menu::with_items(
+        app,
+        &[&show_hide, &new_chat, &settings, &close_to_tray_item, &quit],
+    )?;
+
+    Ok((menu, close_to_tray_item))
+}
+
+/// 处理托盘菜单点击事件。
+pub fn handle_menu_event(app: &AppHandle, close_to_tray_item: &CheckMenuItem<Wry>, event: MenuEvent) {
+    match event.id().as_ref() {
+        MENU_ID_SHOW_HIDE => shortcut::toggle_main_window(app),
+        MENU_ID_NEW_CHAT => {
+            let _ = app.emit("agent://new-chat", ());
+        }
+        MENU_ID_SETTINGS => {
+            let _ = app.emit("agent://navigate", "/settings");
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        MENU_ID_CLOSE_TO_TRAY => {
+            // 菜单项点击后勾选状态已经由 Tauri 自动翻转，这里只需要把新状态持久化。
+            let close_to_tray = close_to_tray_item.is_checked().unwrap_or(true);
+            if let Some(state) = app.try_state::<ConfigState>() {
+                if let Ok(mut config) = state.0.lock() {
+                    config.close_to_tray = close_to_tray;
+                    let _ = config.save(app);
+                }
+            }
+        }
+        MENU_ID_QUIT => {
+            // 退出前先让所有在途 agent 任务、以及 run_tool 启动的子进程停止，再结束进程。
+            if let Some(state) = app.try_state::<AgentState>() {
+                if let Ok(mut tasks) = state.tasks.lock() {
+                    for (_, handles) in tasks.drain() {
+                        handles.abort();
+                    }
+                }
+                if let Ok(mut tool_tasks) = state.tool_tasks.lock() {
+                    for (_, handle) in tool_tasks.drain() {
+                        handle.abort();
+                    }
+                }
+            }
+            app.exit(0);
+        }
+        _ => {}
+    }
+}